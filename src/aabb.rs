@@ -0,0 +1,87 @@
+use point::Point3;
+use ray::Ray3;
+
+#[derive(Copy)]
+pub struct Aabb {
+    pub min: Point3,
+    pub max: Point3
+}
+
+impl Aabb {
+    pub fn new(min: Point3, max: Point3) -> Aabb {
+        Aabb {
+            min: min,
+            max: max
+        }
+    }
+
+    pub fn union(self, other: Aabb) -> Aabb {
+        Aabb {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max)
+        }
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        self.min + (self.max - self.min) * 0.5
+    }
+
+    // Used by the BVH builder's surface-area heuristic: the expected
+    // number of rays hitting a box is proportional to its surface area.
+    pub fn surface_area(&self) -> f32 {
+        let (dx, dy, dz) = (self.max - self.min).channels();
+        2. * (dx*dy + dy*dz + dz*dx)
+    }
+
+    // Standard slab test, narrowing [0, t_max] against each pair of
+    // axis-aligned planes in turn. Returns the entry time if the ray
+    // passes through the box before t_max.
+    pub fn hit(&self, ray: Ray3, t_max: f32) -> Option<f32> {
+        let (ox, oy, oz) = ray.start.channels();
+        let (dx, dy, dz) = ray.dir.channels();
+        let (min_x, min_y, min_z) = self.min.channels();
+        let (max_x, max_y, max_z) = self.max.channels();
+
+        let mut t_min = 0.;
+        let mut t_max = t_max;
+
+        let inv_dx = 1. / dx;
+        let (mut t0, mut t1) = ((min_x - ox) * inv_dx, (max_x - ox) * inv_dx);
+        if inv_dx < 0. {
+            let tmp = t0; t0 = t1; t1 = tmp;
+        }
+        if t0 > t_min { t_min = t0; }
+        if t1 < t_max { t_max = t1; }
+        if t_max <= t_min {
+            return None;
+        }
+
+        let inv_dy = 1. / dy;
+        let (mut t0, mut t1) = ((min_y - oy) * inv_dy, (max_y - oy) * inv_dy);
+        if inv_dy < 0. {
+            let tmp = t0; t0 = t1; t1 = tmp;
+        }
+        if t0 > t_min { t_min = t0; }
+        if t1 < t_max { t_max = t1; }
+        if t_max <= t_min {
+            return None;
+        }
+
+        let inv_dz = 1. / dz;
+        let (mut t0, mut t1) = ((min_z - oz) * inv_dz, (max_z - oz) * inv_dz);
+        if inv_dz < 0. {
+            let tmp = t0; t0 = t1; t1 = tmp;
+        }
+        if t0 > t_min { t_min = t0; }
+        if t1 < t_max { t_max = t1; }
+        if t_max <= t_min {
+            return None;
+        }
+
+        Some(t_min)
+    }
+}
+
+pub trait Bounded {
+    fn bounds(&self) -> Aabb;
+}
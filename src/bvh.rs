@@ -0,0 +1,278 @@
+use std::f32;
+
+use ray::Ray3;
+use scene::{Scene, Intersection, TagObject};
+use aabb::{Aabb, Bounded};
+
+// A binary BVH over an owned `Vec<T>`. Unlike `impl Scene for [T]`, which
+// scans every object for every ray, this prunes whole subtrees using each
+// node's bounding box. `ObjectId` stays `(usize, T::ObjectId)` keyed by the
+// object's original index, so `TagObject`'s re-intersection suppression
+// works exactly as it does for `[T]`.
+pub struct Bvh<T> {
+    objects: Vec<T>,
+    root: Node
+}
+
+enum Node {
+    // A BVH with no objects in it; never hit by anything.
+    Empty,
+    Leaf {
+        bounds: Aabb,
+        index: usize
+    },
+    Interior {
+        bounds: Aabb,
+        left: Box<Node>,
+        right: Box<Node>
+    }
+}
+
+impl<T: Bounded> Bvh<T> {
+    pub fn new(objects: Vec<T>) -> Bvh<T> {
+        let mut indices: Vec<usize> = (0..objects.len()).collect();
+        let root = build(&objects, &mut indices[..]);
+        Bvh {
+            objects: objects,
+            root: root
+        }
+    }
+}
+
+// Children of an Interior node are always built from a nonempty slice
+// of indices, so Empty never appears below the root.
+fn non_empty_bounds(node: &Node) -> &Aabb {
+    match *node {
+        Node::Empty => unreachable!(),
+        Node::Leaf { ref bounds, .. } => bounds,
+        Node::Interior { ref bounds, .. } => bounds
+    }
+}
+
+fn object_bounds<T: Bounded>(objects: &[T], index: usize) -> Aabb {
+    objects[index].bounds()
+}
+
+fn axis_value(channels: (f32, f32, f32), axis: usize) -> f32 {
+    match axis {
+        0 => channels.0,
+        1 => channels.1,
+        _ => channels.2
+    }
+}
+
+// Number of candidate split planes to evaluate per axis. 12 is the
+// usual choice in SAH BVH builders: enough to find a good split while
+// evaluating the cost once per bin boundary rather than once per object.
+const NUM_BINS: usize = 12;
+
+fn build<T: Bounded>(objects: &[T], indices: &mut [usize]) -> Node {
+    if indices.len() == 0 {
+        return Node::Empty;
+    }
+
+    if indices.len() == 1 {
+        return Node::Leaf {
+            bounds: object_bounds(objects, indices[0]),
+            index: indices[0]
+        };
+    }
+
+    let mut bounds = object_bounds(objects, indices[0]);
+    for &i in indices[1..].iter() {
+        bounds = bounds.union(object_bounds(objects, i));
+    }
+
+    // Bin along whichever axis the centroids are most spread out on.
+    let (mut centroid_min, mut centroid_max) = {
+        let c = object_bounds(objects, indices[0]).centroid().channels();
+        (c, c)
+    };
+    for &i in indices.iter() {
+        let c = object_bounds(objects, i).centroid().channels();
+        centroid_min = (centroid_min.0.min(c.0), centroid_min.1.min(c.1), centroid_min.2.min(c.2));
+        centroid_max = (centroid_max.0.max(c.0), centroid_max.1.max(c.1), centroid_max.2.max(c.2));
+    }
+    let spread = (
+        centroid_max.0 - centroid_min.0,
+        centroid_max.1 - centroid_min.1,
+        centroid_max.2 - centroid_min.2
+    );
+    let axis = if spread.0 >= spread.1 && spread.0 >= spread.2 {
+        0
+    } else if spread.1 >= spread.2 {
+        1
+    } else {
+        2
+    };
+    let axis_extent = axis_value(spread, axis);
+    let axis_min = axis_value(centroid_min, axis);
+
+    if axis_extent <= 0. {
+        // Every centroid coincides along every axis; there's no
+        // meaningful split left, so just divide the indices in half.
+        return split_indices(objects, bounds, indices, indices.len() / 2);
+    }
+
+    let bin_of = |i: usize| -> usize {
+        let t = (axis_value(object_bounds(objects, i).centroid().channels(), axis) - axis_min) / axis_extent;
+        let bin = (t * NUM_BINS as f32) as usize;
+        if bin >= NUM_BINS { NUM_BINS - 1 } else { bin }
+    };
+
+    let mut bin_bounds: [Option<Aabb>; NUM_BINS] = [None; NUM_BINS];
+    let mut bin_count = [0usize; NUM_BINS];
+    for &i in indices.iter() {
+        let bin = bin_of(i);
+        let obj_bounds = object_bounds(objects, i);
+        bin_bounds[bin] = Some(match bin_bounds[bin] {
+            Some(b) => b.union(obj_bounds),
+            None => obj_bounds
+        });
+        bin_count[bin] += 1;
+    }
+
+    // Sweep from the left and from the right to get, for every candidate
+    // split plane between bin `k` and bin `k + 1`, the bounds and count
+    // of everything on each side.
+    let mut prefix_bounds: [Option<Aabb>; NUM_BINS] = [None; NUM_BINS];
+    let mut prefix_count = [0usize; NUM_BINS];
+    let mut running_bounds = None;
+    let mut running_count = 0;
+    for bin in 0..NUM_BINS {
+        if let Some(b) = bin_bounds[bin] {
+            running_bounds = Some(match running_bounds {
+                Some(a) => a.union(b),
+                None => b
+            });
+        }
+        running_count += bin_count[bin];
+        prefix_bounds[bin] = running_bounds;
+        prefix_count[bin] = running_count;
+    }
+
+    let mut suffix_bounds: [Option<Aabb>; NUM_BINS] = [None; NUM_BINS];
+    let mut suffix_count = [0usize; NUM_BINS];
+    running_bounds = None;
+    running_count = 0;
+    for bin in (0..NUM_BINS).rev() {
+        if let Some(b) = bin_bounds[bin] {
+            running_bounds = Some(match running_bounds {
+                Some(a) => a.union(b),
+                None => b
+            });
+        }
+        running_count += bin_count[bin];
+        suffix_bounds[bin] = running_bounds;
+        suffix_count[bin] = running_count;
+    }
+
+    let mut best_split = None;
+    let mut best_cost = f32::INFINITY;
+    for split in 0..NUM_BINS - 1 {
+        let left_count = prefix_count[split];
+        let right_count = suffix_count[split + 1];
+        if left_count == 0 || right_count == 0 {
+            continue;
+        }
+
+        let cost = prefix_bounds[split].unwrap().surface_area() * left_count as f32
+            + suffix_bounds[split + 1].unwrap().surface_area() * right_count as f32;
+        if cost < best_cost {
+            best_cost = cost;
+            best_split = Some((split, left_count));
+        }
+    }
+
+    match best_split {
+        Some((_, left_count)) => {
+            indices.sort_by(|&a, &b| {
+                let ac = axis_value(object_bounds(objects, a).centroid().channels(), axis);
+                let bc = axis_value(object_bounds(objects, b).centroid().channels(), axis);
+                ac.partial_cmp(&bc).unwrap()
+            });
+            split_indices(objects, bounds, indices, left_count)
+        },
+        // Every object landed in the same bin: fall back to an even split.
+        None => split_indices(objects, bounds, indices, indices.len() / 2)
+    }
+}
+
+fn split_indices<T: Bounded>(objects: &[T], bounds: Aabb, indices: &mut [usize], split_at: usize) -> Node {
+    let (left_indices, right_indices) = indices.split_at_mut(split_at);
+    Node::Interior {
+        bounds: bounds,
+        left: Box::new(build(objects, left_indices)),
+        right: Box::new(build(objects, right_indices))
+    }
+}
+
+impl<T: Scene + Bounded> Scene for Bvh<T> {
+    type ObjectId = (usize, T::ObjectId);
+    type OutDist = TagObject<usize, T::OutDist>;
+
+    fn intersect(&self, ray: Ray3, previous: Option<(usize, T::ObjectId)>) -> Option<Intersection<TagObject<usize, T::OutDist>>> {
+        let mut best = None;
+        intersect_node(&self.objects, &self.root, ray, previous, &mut best);
+        best
+    }
+}
+
+fn intersect_node<T: Scene + Bounded>(
+    objects: &[T],
+    node: &Node,
+    ray: Ray3,
+    previous: Option<(usize, T::ObjectId)>,
+    best: &mut Option<Intersection<TagObject<usize, T::OutDist>>>
+) {
+    let bounds = match *node {
+        Node::Empty => return,
+        Node::Leaf { ref bounds, .. } => bounds,
+        Node::Interior { ref bounds, .. } => bounds
+    };
+
+    let t_max = match *best {
+        Some(ref cur_best) => cur_best.time,
+        None => f32::INFINITY
+    };
+
+    if bounds.hit(ray, t_max).is_none() {
+        return;
+    }
+
+    match *node {
+        Node::Empty => unreachable!(),
+        Node::Leaf { index, .. } => {
+            let prev = match previous {
+                Some((prev_index, prev_obj)) if prev_index == index => Some(prev_obj),
+                _ => None
+            };
+
+            if let Some(intersection) = objects[index].intersect(ray, prev) {
+                let better = match *best {
+                    Some(ref cur_best) => intersection.time < cur_best.time,
+                    None => true
+                };
+
+                if better {
+                    *best = Some(intersection.map_dist(|dist| TagObject::new(index, dist)));
+                }
+            }
+        },
+        Node::Interior { ref left, ref right, .. } => {
+            // Visit whichever child the ray reaches first, so the other one
+            // can be pruned by a tighter `best.time` if the first subtree
+            // already contains a hit.
+            let left_t = non_empty_bounds(left).hit(ray, t_max);
+            let right_t = non_empty_bounds(right).hit(ray, t_max);
+
+            let (first, second) = match (left_t, right_t) {
+                (Some(lt), Some(rt)) if rt < lt => (right, left),
+                _ => (left, right)
+            };
+
+            intersect_node(objects, first, ray, previous, best);
+            intersect_node(objects, second, ray, previous, best);
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::num::Float;
+use std::f32::consts::PI;
+
+use rand;
+use rand::distributions::IndependentSample;
+use rand::Rng;
+
+use point::Point3;
+use vec::Vec3;
+use ray::Ray3;
+
+// A thin-lens camera. Rays leave a disk of radius `aperture/2` around
+// `look_from` and are aimed so that everything on the focus plane (the
+// plane through `look_at` perpendicular to the view direction) stays
+// sharp, while everything off it blurs in proportion to its distance
+// from that plane.
+#[derive(Copy)]
+pub struct Camera {
+    origin: Point3,
+    lower_left_corner: Point3,
+    horizontal: Vec3,
+    vertical: Vec3,
+    u: Vec3,
+    v: Vec3,
+    lens_radius: f32
+}
+
+impl Camera {
+    pub fn new(look_from: Point3, look_at: Point3, up: Vec3, vfov_degrees: f32, aspect: f32, aperture: f32, focus_dist: f32) -> Camera {
+        let theta = vfov_degrees * PI / 180.;
+        let half_height = (theta / 2.).tan();
+        let half_width = aspect * half_height;
+
+        let w = (look_from - look_at) * (1. / (look_from - look_at).mag2().sqrt());
+        // `w` points backward (away from the view direction), so crossing
+        // it with `up` this way round -- rather than `up.cross(w)` --
+        // keeps `u` pointing toward camera-right rather than camera-left.
+        let u = w.cross(up) * (1. / w.cross(up).mag2().sqrt());
+        let v = u.cross(w);
+
+        Camera {
+            origin: look_from,
+            lower_left_corner: look_from + (-u * half_width - v * half_height - w) * focus_dist,
+            horizontal: u * (2. * half_width * focus_dist),
+            vertical: v * (2. * half_height * focus_dist),
+            u: u,
+            v: v,
+            lens_radius: aperture / 2.
+        }
+    }
+
+    // `s` and `t` are normalized image-plane coordinates in [0, 1],
+    // with (0, 0) at the lower-left corner of the focus plane.
+    pub fn get_ray<R: Rng>(&self, s: f32, t: f32, rng: &mut R) -> Ray3 {
+        let (lens_x, lens_y) = rand_in_unit_disk(rng);
+        let offset = (self.u * lens_x + self.v * lens_y) * self.lens_radius;
+
+        let start = self.origin + offset;
+        let target = self.lower_left_corner + self.horizontal * s + self.vertical * t;
+        Ray3 {
+            start: start,
+            dir: target - start
+        }
+    }
+}
+
+fn rand_in_unit_disk<R: Rng>(rng: &mut R) -> (f32, f32) {
+    let range = rand::distributions::Range::new(-1., 1.);
+    loop {
+        let x = range.ind_sample(rng);
+        let y = range.ind_sample(rng);
+        if x*x + y*y < 1. {
+            return (x, y);
+        }
+    }
+}
@@ -8,22 +8,24 @@ use rand::Rng;
 use point::Point3;
 use vec::Vec3;
 use ray::Ray3;
-use distribution::{Distribution, Const};
+use distribution::{Distribution, Const, Or};
+use sphere::SphereSource;
 
+#[derive(Copy)]
 pub struct Material<Refl> {
     reflection: Refl,
-    emitted_radiance: f32
+    emitted_radiance: Vec3
 }
 
 impl<Refl> Material<Refl> {
-    pub fn new(reflection: Refl, emitted_radiance: f32) -> Material<Refl> {
+    pub fn new(reflection: Refl, emitted_radiance: Vec3) -> Material<Refl> {
         Material {
             reflection: reflection,
             emitted_radiance: emitted_radiance
         }
     }
 
-    pub fn emitted(&self) -> f32 {
+    pub fn emitted(&self) -> Vec3 {
         self.emitted_radiance
     }
 
@@ -33,11 +35,22 @@ impl<Refl> Material<Refl> {
 }
 
 pub trait Reflection<ObjectId> {
-    type OutDist: Distribution<Output=(f32, Ray3, ObjectId)>;
+    type OutDist: Distribution<Output=(Vec3, Ray3, ObjectId)>;
     fn reflect(&self, incoming: Vec3, normal: Vec3, point: Point3, object: ObjectId) -> Self::OutDist;
 }
 
-pub struct Diffuse;
+#[derive(Copy)]
+pub struct Diffuse {
+    albedo: Vec3
+}
+
+impl Diffuse {
+    pub fn new(albedo: Vec3) -> Diffuse {
+        Diffuse {
+            albedo: albedo
+        }
+    }
+}
 
 impl<ObjectId: Clone> Reflection<ObjectId> for Diffuse {
     type OutDist = DiffuseDist<ObjectId>;
@@ -45,6 +58,7 @@ impl<ObjectId: Clone> Reflection<ObjectId> for Diffuse {
         DiffuseDist {
             point: point,
             normal: normal,
+            albedo: self.albedo,
             object: object
         }
     }
@@ -53,31 +67,39 @@ impl<ObjectId: Clone> Reflection<ObjectId> for Diffuse {
 pub struct DiffuseDist<ObjectId> {
     point: Point3,
     normal: Vec3,
+    albedo: Vec3,
     object: ObjectId
 }
 
 impl<ObjectId: Clone> Distribution for DiffuseDist<ObjectId> {
-    type Output = (f32, Ray3, ObjectId);
-
-    fn sample<R: Rng>(&self, rng: &mut R) -> (f32, Ray3, ObjectId) {
-        fn rand_sphere<R: rand::Rng>(rng: &mut R) -> Vec3 {
-           let z = rand::distributions::Range::new(-1., 1.).ind_sample(rng);
-           let r = (1. - z*z).sqrt();
-           let angle = rand::distributions::Range::new(0., PI_2).ind_sample(rng);
-           Vec3::new(r*angle.cos(), r*angle.sin(), z)
-       }
-
-        let cand_dir = rand_sphere(rng);
-        let dir = if cand_dir.dot(self.normal) < 0. {
-            -cand_dir
+    type Output = (Vec3, Ray3, ObjectId);
+
+    fn sample<R: Rng>(&self, rng: &mut R) -> (Vec3, Ray3, ObjectId) {
+        // Build an orthonormal basis (t, bt, n) around the normal, picking
+        // whichever of the x and y axes is less aligned with it to cross
+        // against so the basis never degenerates.
+        let n = self.normal * (1. / self.normal.mag2().sqrt());
+        let axis = if n.dot(Vec3::new(1., 0., 0.)).abs() < n.dot(Vec3::new(0., 1., 0.)).abs() {
+            Vec3::new(1., 0., 0.)
         } else {
-            cand_dir
+            Vec3::new(0., 1., 0.)
         };
+        let t = axis.cross(n) * (1. / axis.cross(n).mag2().sqrt());
+        let bt = n.cross(t);
+
+        let r1 = rand::distributions::Range::new(0., 1.).ind_sample(rng);
+        let r2: f32 = rand::distributions::Range::new(0., 1.).ind_sample(rng);
+        let phi = PI_2 * r1;
+        let r = r2.sqrt();
+        let (local_x, local_y, local_z) = (r*phi.cos(), r*phi.sin(), (1. - r2).sqrt());
 
-        let scale = dir.dot(self.normal) / self.normal.mag2().sqrt();
+        let dir = t*local_x + bt*local_y + n*local_z;
 
+        // The cos θ / π sampling pdf exactly cancels the Lambertian
+        // brdf's albedo/π * cos θ, so the throughput multiplier is just
+        // the albedo.
         (
-            scale,
+            self.albedo,
             Ray3 {
                 start: self.point,
                 dir: dir
@@ -87,19 +109,87 @@ impl<ObjectId: Clone> Distribution for DiffuseDist<ObjectId> {
     }
 }
 
-pub struct Specular;
+#[derive(Copy)]
+pub struct Specular {
+    albedo: Vec3
+}
+
+impl Specular {
+    pub fn new(albedo: Vec3) -> Specular {
+        Specular {
+            albedo: albedo
+        }
+    }
+}
 
 impl<ObjectId: Clone> Reflection<ObjectId> for Specular {
-    type OutDist = Const<(f32, Ray3, ObjectId)>;
-    fn reflect(&self, incoming: Vec3, normal: Vec3, point: Point3, object: ObjectId) -> Const<(f32, Ray3, ObjectId)> {
+    type OutDist = Const<(Vec3, Ray3, ObjectId)>;
+    fn reflect(&self, incoming: Vec3, normal: Vec3, point: Point3, object: ObjectId) -> Const<(Vec3, Ray3, ObjectId)> {
         let projected = normal * incoming.dot(normal) / normal.mag2();
         Const::new((
-            1.,
+            self.albedo,
             Ray3 {
                 start: point,
-                dir: -incoming - projected * 2.
+                dir: incoming - projected * 2.
             },
             object
         ))
     }
 }
+
+// Glass. Relies on Sphere tagging each hit with the SphereSource the ray
+// was travelling through, so we know which side of the interface we're
+// on and can flip the normal and invert the index of refraction to match.
+#[derive(Copy)]
+pub struct Dielectric {
+    ior: f32
+}
+
+impl Dielectric {
+    pub fn new(ior: f32) -> Dielectric {
+        Dielectric {
+            ior: ior
+        }
+    }
+}
+
+impl Reflection<SphereSource> for Dielectric {
+    type OutDist = Or<Const<(Vec3, Ray3, SphereSource)>, Const<(Vec3, Ray3, SphereSource)>>;
+    fn reflect(&self, incoming: Vec3, normal: Vec3, point: Point3, object: SphereSource) -> Or<Const<(Vec3, Ray3, SphereSource)>, Const<(Vec3, Ray3, SphereSource)>> {
+        let d = incoming * (1. / incoming.mag2().sqrt());
+        let unit_normal = normal * (1. / normal.mag2().sqrt());
+
+        // `n` always faces against the incoming ray.
+        let (eta, n, entering_object) = match object {
+            SphereSource::Outside => (1. / self.ior, unit_normal, SphereSource::Inside),
+            SphereSource::Inside => (self.ior, -unit_normal, SphereSource::Outside)
+        };
+
+        let cos_i = -d.dot(n);
+        let sin2_t = eta*eta*(1. - cos_i*cos_i);
+
+        let reflected_sample = (Vec3::new(1., 1., 1.), Ray3 { start: point, dir: d + n * (2. * cos_i) }, object);
+
+        if sin2_t > 1. {
+            // Total internal reflection: no refracted ray exists, so
+            // always take the mirror direction, same as Specular.
+            return Const::new(reflected_sample).or(Const::new(reflected_sample), 1.);
+        }
+
+        let cos_t = (1. - sin2_t).sqrt();
+        let refracted = Const::new((
+            Vec3::new(1., 1., 1.),
+            Ray3 { start: point, dir: d * eta + n * (eta * cos_i - cos_t) },
+            entering_object
+        ));
+
+        let r0 = ((1. - eta) / (1. + eta)).powi(2);
+        let schlick = r0 + (1. - r0) * (1. - cos_i).powi(5);
+
+        // Reflected with probability `schlick`, refracted otherwise;
+        // deferring the choice to sample time (rather than rolling the
+        // dice here in `reflect`) lets multiple samples of the same hit
+        // explore both outcomes.
+        refracted.or(Const::new(reflected_sample), schlick)
+    }
+}
@@ -0,0 +1,53 @@
+//! A minimal Wavefront OBJ loader: just enough to pull `v` and `f` lines
+//! out of a mesh exported from a modelling tool and turn them into
+//! triangles sharing one material.
+use std::old_io::{File, BufferedReader};
+
+use point::Point3;
+use material::Material;
+use triangle::Triangle;
+
+pub fn load<Refl: Clone>(path: &Path, material: Material<Refl>) -> Vec<Triangle<Refl>> {
+    let mut reader = BufferedReader::new(File::open(path).unwrap());
+
+    let mut vertices = Vec::new();
+    let mut triangles = Vec::new();
+
+    for line in reader.lines() {
+        let line = line.unwrap();
+        let mut words = line.trim().split(' ').filter(|word| !word.is_empty());
+
+        match words.next() {
+            Some("v") => {
+                let x = words.next().unwrap().parse::<f32>().unwrap();
+                let y = words.next().unwrap().parse::<f32>().unwrap();
+                let z = words.next().unwrap().parse::<f32>().unwrap();
+                vertices.push(Point3::new(x, y, z));
+            },
+            Some("f") => {
+                // Each word is "v", "v/vt", or "v/vt/vn"; we only care
+                // about the vertex index, and OBJ indices are 1-based.
+                let indices: Vec<usize> = words.map(|word| {
+                    word.split('/').next().unwrap().parse::<usize>().unwrap() - 1
+                }).collect();
+
+                // Fan-triangulate faces with more than three vertices.
+                // Malformed or degenerate `f` lines (fewer than 3
+                // indices) just contribute no triangles.
+                if indices.len() >= 3 {
+                    for i in 1..indices.len() - 1 {
+                        triangles.push(Triangle::new(
+                            vertices[indices[0]],
+                            vertices[indices[i]],
+                            vertices[indices[i + 1]],
+                            material.clone()
+                        ));
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    triangles
+}
@@ -39,6 +39,7 @@ impl<Refl: Reflection<()>> Scene for Plane<Refl> {
                 Some(Intersection {
                     time: time,
                     emitted: self.material.emitted(),
+                    normal: self.normal,
                     reflection: self.material.reflection().reflect(
                         ray.dir,
                         self.normal,
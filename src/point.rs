@@ -12,6 +12,26 @@ impl Point3 {
 			inner: Vec3::new(x, y, z)
 		}
 	}
+
+	pub fn channels(self) -> (f32, f32, f32) {
+		self.inner.channels()
+	}
+
+	pub fn to_vec(self) -> Vec3 {
+		self.inner
+	}
+
+	pub fn from_vec(v: Vec3) -> Point3 {
+		Point3 { inner: v }
+	}
+
+	pub fn min(self, other: Point3) -> Point3 {
+		Point3 { inner: self.inner.min(other.inner) }
+	}
+
+	pub fn max(self, other: Point3) -> Point3 {
+		Point3 { inner: self.inner.max(other.inner) }
+	}
 }
 
 impl Sub<Point3> for Point3 {
@@ -1,24 +1,22 @@
-use std::num::Float;
-
 use rand::Rng;
 
-use point::Point3;
 use vec::Vec3;
 use ray::Ray3;
 use distribution::Distribution;
-use material::{Material, Reflection};
 
 pub struct Intersection<OutDist> {
     pub time: f32,
-    pub emitted: f32,
+    pub emitted: Vec3,
+    pub normal: Vec3,
     pub reflection: OutDist
 }
 
 impl<OutDist> Intersection<OutDist> {
-    fn map_dist<NewOutDist, F: FnOnce(OutDist) -> NewOutDist>(self, func: F) -> Intersection<NewOutDist> {
+    pub fn map_dist<NewOutDist, F: FnOnce(OutDist) -> NewOutDist>(self, func: F) -> Intersection<NewOutDist> {
         Intersection {
             time: self.time,
             emitted: self.emitted,
+            normal: self.normal,
             reflection: func(self.reflection)
         }
     }
@@ -27,142 +25,21 @@ impl<OutDist> Intersection<OutDist> {
 pub trait Scene {
     // TODO: Should this have a "not present" id?
     type ObjectId: Copy;
-    type OutDist: Distribution<Output=(f32, Ray3, Self::ObjectId)>;
+    type OutDist: Distribution<Output=(Vec3, Ray3, Self::ObjectId)>;
 
     fn intersect(&self, ray: Ray3, previous: Option<Self::ObjectId>) -> Option<Intersection<Self::OutDist>>;
 }
 
-pub struct Sphere<Refl> {
-    center: Point3,
-    radius: f32,
-    material: Material<Refl>
-}
-
-impl<Refl> Sphere<Refl> {
-    pub fn new(center: Point3, radius: f32, material: Material<Refl>) -> Sphere<Refl> {
-        Sphere {
-            center: center,
-            radius: radius,
-            material: material
-        }
-    }
-}
-
-#[derive(Copy, Clone)]
-enum SphereSource {
-    Inside,
-    Outside
-}
-
-impl<Refl: Reflection<SphereSource>> Scene for Sphere<Refl> {
-    type ObjectId = SphereSource;
-    type OutDist = Refl::OutDist;
-    fn intersect(&self, ray: Ray3, previous: Option<SphereSource>) -> Option<Intersection<Refl::OutDist>> {
-        if let Some(SphereSource::Outside) = previous {
-            return None;
-        }
-
-        let offset = ray.start - self.center;
-
-        let a = ray.dir.mag2();
-        let b = 2. * offset.dot(ray.dir);
-        let c = offset.mag2() - self.radius*self.radius;
-
-        let descrim = b*b - 4.*a*c;
-        if descrim < 0. {
-            return None;
-        }
-
-        let time = {
-            let t1 = (-b - descrim.sqrt()) / (2. * a);
-            let t2 = (-b + descrim.sqrt()) / (2. * a);
-            if previous.is_none() && t1 > 0. {
-                t1
-            } else if t2 > 0. {
-                t2
-            } else {
-                return None;
-            }
-        };
-
-        let p = ray.start + ray.dir * time;
-        let normal = p - self.center;
-        Some(Intersection {
-            time: time,
-            emitted: self.material.emitted(),
-            reflection: self.material.reflection().reflect(
-                ray.dir,
-                normal,
-                p,
-                previous.unwrap_or(if c < 0. {
-                    SphereSource::Inside
-                } else {
-                    SphereSource::Outside
-                })
-            )
-        })
-    }
-}
-
-pub struct Plane<Refl> {
-    origin: Point3,
-    normal: Vec3,
-    material: Material<Refl>
-}
-
-impl<Refl> Plane<Refl> {
-    pub fn new(origin: Point3, normal: Vec3, material: Material<Refl>) -> Plane<Refl> {
-        Plane {
-            origin: origin,
-            normal: normal,
-            material: material
-        }
-    }
-}
-
-impl<Refl: Reflection<()>> Scene for Plane<Refl> {
-    type ObjectId = ();
-    type OutDist = Refl::OutDist;
-    fn intersect(&self, ray: Ray3, previous: Option<()>) -> Option<Intersection<Refl::OutDist>> {
-        if let Some(()) = previous {
-            return None;
-        }
-
-        let divisor = ray.dir.dot(self.normal);
-        if divisor == 0. {
-            None
-        } else {
-            let offset = ray.start - self.origin;
-            let time = -offset.dot(self.normal) / divisor;
-            if time > 0. {
-                let point = ray.start + ray.dir * time;
-                Some(Intersection {
-                    time: time,
-                    emitted: self.material.emitted(),
-                    reflection: self.material.reflection().reflect(
-                        ray.dir,
-                        self.normal,
-                        point,
-                        ()
-                    )
-                })
-            } else {
-                None
-            }
-        }
-    }
-}
-
 #[derive(Copy)]
-enum Choice<A, B> {
+pub enum Choice<A, B> {
     OptA(A),
     OptB(B)
 }
 
-impl<AObj, BObj, A: Distribution<Output=(f32, Ray3, AObj)>, B: Distribution<Output=(f32, Ray3, BObj)>> Distribution for Choice<A, B> {
-    type Output = (f32, Ray3, Choice<AObj, BObj>);
+impl<AObj, BObj, A: Distribution<Output=(Vec3, Ray3, AObj)>, B: Distribution<Output=(Vec3, Ray3, BObj)>> Distribution for Choice<A, B> {
+    type Output = (Vec3, Ray3, Choice<AObj, BObj>);
 
-    fn sample<R: Rng>(&self, rng: &mut R) -> (f32, Ray3, Choice<AObj, BObj>) {
+    fn sample<R: Rng>(&self, rng: &mut R) -> (Vec3, Ray3, Choice<AObj, BObj>) {
         match *self {
             Choice::OptA(ref a) => {
                 let (scale, ray, obj) = a.sample(rng);
@@ -199,15 +76,24 @@ impl<A: Scene, B: Scene> Scene for (A, B) {
     }
 }
 
-struct TagObject<T, Dist> {
+pub struct TagObject<T, Dist> {
     tag: T,
     dist: Dist
 }
 
-impl<T: Clone, O, Dist: Distribution<Output=(f32, Ray3, O)>> Distribution for TagObject<T, Dist> {
-    type Output = (f32, Ray3, (T, O));
+impl<T, Dist> TagObject<T, Dist> {
+    pub fn new(tag: T, dist: Dist) -> TagObject<T, Dist> {
+        TagObject {
+            tag: tag,
+            dist: dist
+        }
+    }
+}
+
+impl<T: Clone, O, Dist: Distribution<Output=(Vec3, Ray3, O)>> Distribution for TagObject<T, Dist> {
+    type Output = (Vec3, Ray3, (T, O));
 
-    fn sample<R: Rng>(&self, rng: &mut R) -> (f32, Ray3, (T, O)) {
+    fn sample<R: Rng>(&self, rng: &mut R) -> (Vec3, Ray3, (T, O)) {
         let (scale, ray, obj) = self.dist.sample(rng);
         (scale, ray, (self.tag.clone(), obj))
     }
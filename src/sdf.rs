@@ -0,0 +1,97 @@
+use std::num::Float;
+
+use point::Point3;
+use vec::Vec3;
+use ray::Ray3;
+use material::{Material, Reflection};
+use scene::{Scene, Intersection};
+
+// A signed distance field: `dist` returns (a lower bound on) the
+// distance from `p` to the surface, negative inside it. Lets shapes with
+// no closed-form ray intersection -- tori, rounded boxes, CSG unions of
+// other fields -- be rendered by sphere tracing instead.
+pub trait Sdf {
+    fn dist(&self, p: Point3) -> f32;
+}
+
+// How close to the surface a marched ray has to get to count as a hit.
+const SURFACE_EPSILON: f32 = 1e-4;
+// Step size for the central-difference gradient used as the normal.
+const NORMAL_EPSILON: f32 = 1e-4;
+// Give up -- treat the ray as missing -- past this many steps or once
+// it's marched this far from its origin.
+const MAX_STEPS: usize = 200;
+const T_MAX: f32 = 1000.;
+// How far to nudge the next ray's origin off the surface along the
+// normal. Since sphere tracing has no analytic previous-hit tag to
+// suppress self-intersection with, this plays that role instead.
+const OFFSET_EPSILON: f32 = 1e-3;
+
+pub struct RayMarched<D, Refl> {
+    sdf: D,
+    material: Material<Refl>
+}
+
+impl<D, Refl> RayMarched<D, Refl> {
+    pub fn new(sdf: D, material: Material<Refl>) -> RayMarched<D, Refl> {
+        RayMarched {
+            sdf: sdf,
+            material: material
+        }
+    }
+}
+
+impl<D: Sdf, Refl> RayMarched<D, Refl> {
+    fn normal_at(&self, p: Point3) -> Vec3 {
+        let dx = Vec3::new(NORMAL_EPSILON, 0., 0.);
+        let dy = Vec3::new(0., NORMAL_EPSILON, 0.);
+        let dz = Vec3::new(0., 0., NORMAL_EPSILON);
+
+        let gradient = Vec3::new(
+            self.sdf.dist(p + dx) - self.sdf.dist(p + (-dx)),
+            self.sdf.dist(p + dy) - self.sdf.dist(p + (-dy)),
+            self.sdf.dist(p + dz) - self.sdf.dist(p + (-dz))
+        );
+        gradient * (1. / gradient.mag2().sqrt())
+    }
+}
+
+impl<D: Sdf, Refl: Reflection<()>> Scene for RayMarched<D, Refl> {
+    // No previous hit to suppress: the offset along the normal below
+    // does that job instead, so there's nothing meaningful to tag a hit
+    // with.
+    type ObjectId = ();
+    type OutDist = Refl::OutDist;
+
+    fn intersect(&self, ray: Ray3, _previous: Option<()>) -> Option<Intersection<Refl::OutDist>> {
+        // The step distances `dist` returns are in world-space units, so
+        // marching needs a unit direction -- callers (camera rays,
+        // specular/refracted bounces) don't guarantee one. `t` is
+        // rescaled back into `ray.dir`'s own units before being reported,
+        // since that's what the renderer's hit-time convention expects.
+        let dir_len = ray.dir.mag2().sqrt();
+        let dir = ray.dir * (1. / dir_len);
+
+        let mut t = SURFACE_EPSILON;
+        for _ in 0..MAX_STEPS {
+            let p = ray.start + dir * t;
+            let d = self.sdf.dist(p);
+            if d < SURFACE_EPSILON {
+                let normal = self.normal_at(p);
+                return Some(Intersection {
+                    time: t / dir_len,
+                    emitted: self.material.emitted(),
+                    normal: normal,
+                    reflection: self.material.reflection().reflect(ray.dir, normal, p + normal * OFFSET_EPSILON, ())
+                });
+            }
+
+            t += d;
+            if t > T_MAX {
+                return None;
+            }
+        }
+
+        None
+    }
+}
@@ -1,9 +1,11 @@
 use std::num::Float;
 
 use point::Point3;
+use vec::Vec3;
 use ray::Ray3;
 use material::{Material, Reflection};
 use scene::{Scene, Intersection};
+use aabb::{Aabb, Bounded};
 
 pub struct Sphere<Refl> {
     center: Point3,
@@ -21,8 +23,15 @@ impl<Refl> Sphere<Refl> {
     }
 }
 
+impl<Refl> Bounded for Sphere<Refl> {
+    fn bounds(&self) -> Aabb {
+        let r = Vec3::new(self.radius, self.radius, self.radius);
+        Aabb::new(self.center + (-r), self.center + r)
+    }
+}
+
 #[derive(Copy, Clone)]
-enum SphereSource {
+pub enum SphereSource {
     Inside,
     Outside
 }
@@ -63,6 +72,7 @@ impl<Refl: Reflection<SphereSource>> Scene for Sphere<Refl> {
         Some(Intersection {
             time: time,
             emitted: self.material.emitted(),
+            normal: normal,
             reflection: self.material.reflection().reflect(
                 ray.dir,
                 normal,
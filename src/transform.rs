@@ -0,0 +1,77 @@
+use rand::Rng;
+
+use vec::{Vec3, Mat4};
+use ray::Ray3;
+use distribution::Distribution;
+use scene::{Scene, Intersection};
+
+// Places an inner Scene in the world via an arbitrary affine map (so,
+// unlike a plain translation, this also supports rotated and non-
+// uniformly scaled instances -- ellipsoids and oriented boxes out of a
+// unit Sphere/cube, or several placements of the same mesh).
+pub struct Transformed<S> {
+    inner: S,
+    transform: Mat4,
+    inverse: Mat4
+}
+
+impl<S> Transformed<S> {
+    pub fn new(inner: S, transform: Mat4) -> Transformed<S> {
+        Transformed {
+            inner: inner,
+            inverse: transform.inverse(),
+            transform: transform
+        }
+    }
+}
+
+impl<S: Scene> Scene for Transformed<S> {
+    type ObjectId = S::ObjectId;
+    type OutDist = TransformedDist<S::OutDist>;
+
+    fn intersect(&self, ray: Ray3, previous: Option<S::ObjectId>) -> Option<Intersection<TransformedDist<S::OutDist>>> {
+        // `ray.start` is transformed as a point, `ray.dir` as a vector
+        // (no translation). The direction is deliberately left
+        // unnormalized, so the object-space hit time is still the
+        // correct world-space hit time.
+        let object_ray = Ray3 {
+            start: self.inverse.mul_point(ray.start),
+            dir: self.inverse.mul_vec(ray.dir)
+        };
+
+        self.inner.intersect(object_ray, previous).map(|intersection| {
+            let world_normal = self.inverse.transpose().mul_vec(intersection.normal);
+            let transform = self.transform;
+            Intersection {
+                time: intersection.time,
+                emitted: intersection.emitted,
+                normal: world_normal,
+                reflection: TransformedDist {
+                    dist: intersection.reflection,
+                    transform: transform
+                }
+            }
+        })
+    }
+}
+
+pub struct TransformedDist<Dist> {
+    dist: Dist,
+    transform: Mat4
+}
+
+impl<O, Dist: Distribution<Output=(Vec3, Ray3, O)>> Distribution for TransformedDist<Dist> {
+    type Output = (Vec3, Ray3, O);
+
+    fn sample<R: Rng>(&self, rng: &mut R) -> (Vec3, Ray3, O) {
+        let (scale, ray, object) = self.dist.sample(rng);
+        (
+            scale,
+            Ray3 {
+                start: self.transform.mul_point(ray.start),
+                dir: self.transform.mul_vec(ray.dir)
+            },
+            object
+        )
+    }
+}
@@ -0,0 +1,95 @@
+use std::num::Float;
+
+use point::Point3;
+use vec::Vec3;
+use ray::Ray3;
+use material::{Material, Reflection};
+use scene::{Scene, Intersection};
+use aabb::{Aabb, Bounded};
+
+// Below this, the ray is considered parallel to the triangle's plane.
+const EPSILON: f32 = 1e-6;
+
+pub struct Triangle<Refl> {
+    a: Point3,
+    b: Point3,
+    c: Point3,
+    material: Material<Refl>
+}
+
+impl<Refl> Triangle<Refl> {
+    pub fn new(a: Point3, b: Point3, c: Point3, material: Material<Refl>) -> Triangle<Refl> {
+        Triangle {
+            a: a,
+            b: b,
+            c: c,
+            material: material
+        }
+    }
+}
+
+impl<Refl> Bounded for Triangle<Refl> {
+    fn bounds(&self) -> Aabb {
+        Aabb::new(
+            self.a.min(self.b).min(self.c),
+            self.a.max(self.b).max(self.c)
+        )
+    }
+}
+
+impl<Refl: Reflection<()>> Scene for Triangle<Refl> {
+    // chunk0-4 originally asked for an ObjectId that could distinguish
+    // front/back faces the way SphereSource does for spheres, so
+    // two-sided materials would work; chunk1-4's restatement of this
+    // request asked for `()` instead, since a single triangle can't be
+    // re-hit by the ray that just left it (no concave geometry to
+    // self-intersect against) -- there's nothing to tag a hit with. That
+    // waives front/back-aware two-sided materials for triangles: the
+    // geometric normal is left unflipped, same as Sphere/Plane, and it's
+    // up to a direction-agnostic `Reflection` impl to handle both sides.
+    type ObjectId = ();
+    type OutDist = Refl::OutDist;
+    fn intersect(&self, ray: Ray3, previous: Option<()>) -> Option<Intersection<Refl::OutDist>> {
+        if previous.is_some() {
+            return None;
+        }
+
+        // Moller-Trumbore.
+        let e1 = self.b - self.a;
+        let e2 = self.c - self.a;
+
+        let p = ray.dir.cross(e2);
+        let det = e1.dot(p);
+        if det.abs() < EPSILON {
+            return None;
+        }
+        let inv_det = 1. / det;
+
+        let t_vec = ray.start - self.a;
+        let u = t_vec.dot(p) * inv_det;
+        if u < 0. || u > 1. {
+            return None;
+        }
+
+        let q = t_vec.cross(e1);
+        let v = ray.dir.dot(q) * inv_det;
+        if v < 0. || u + v > 1. {
+            return None;
+        }
+
+        let time = e2.dot(q) * inv_det;
+        if time <= 0. {
+            return None;
+        }
+
+        let normal = e1.cross(e2);
+
+        let point = ray.start + ray.dir * time;
+        Some(Intersection {
+            time: time,
+            emitted: self.material.emitted(),
+            normal: normal,
+            reflection: self.material.reflection().reflect(ray.dir, normal, point, ())
+        })
+    }
+}
@@ -1,4 +1,7 @@
-use std::ops::{Add, Sub, Mul};
+use std::num::Float;
+use std::ops::{Add, Sub, Mul, Neg};
+
+use point::Point3;
 
 #[derive(Copy)]
 pub struct Vec3 {
@@ -19,6 +22,45 @@ impl Vec3 {
     pub fn mag2(self) -> f32 {
         self.dot(self)
     }
+
+    pub fn cross(self, other: Vec3) -> Vec3 {
+        Vec3 {
+            vals: [
+                self.vals[1] * other.vals[2] - self.vals[2] * other.vals[1],
+                self.vals[2] * other.vals[0] - self.vals[0] * other.vals[2],
+                self.vals[0] * other.vals[1] - self.vals[1] * other.vals[0]
+            ]
+        }
+    }
+
+    // Used as the Russian-roulette survival probability.
+    pub fn max_channel(self) -> f32 {
+        self.vals[0].max(self.vals[1]).max(self.vals[2])
+    }
+
+    pub fn channels(self) -> (f32, f32, f32) {
+        (self.vals[0], self.vals[1], self.vals[2])
+    }
+
+    pub fn min(self, other: Vec3) -> Vec3 {
+        Vec3 {
+            vals: [
+                self.vals[0].min(other.vals[0]),
+                self.vals[1].min(other.vals[1]),
+                self.vals[2].min(other.vals[2])
+            ]
+        }
+    }
+
+    pub fn max(self, other: Vec3) -> Vec3 {
+        Vec3 {
+            vals: [
+                self.vals[0].max(other.vals[0]),
+                self.vals[1].max(other.vals[1]),
+                self.vals[2].max(other.vals[2])
+            ]
+        }
+    }
 }
 
 impl Add<Vec3> for Vec3 {
@@ -59,3 +101,121 @@ impl Mul<f32> for Vec3 {
         }
     }
 }
+
+// Component-wise product, used to scale a throughput color by a
+// per-channel reflectance.
+impl Mul<Vec3> for Vec3 {
+    type Output = Vec3;
+    fn mul(self, other: Vec3) -> Vec3 {
+        Vec3 {
+            vals: [
+                self.vals[0] * other.vals[0],
+                self.vals[1] * other.vals[1],
+                self.vals[2] * other.vals[2]
+            ]
+        }
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+    fn neg(self) -> Vec3 {
+        Vec3 {
+            vals: [-self.vals[0], -self.vals[1], -self.vals[2]]
+        }
+    }
+}
+
+// A 4x4 homogeneous matrix, used by Transformed to place an object in
+// the world (and, inverted, to map rays back into its object space).
+// Only affine matrices (bottom row [0, 0, 0, 1]) are expected, so points
+// and vectors are multiplied without a perspective divide.
+#[derive(Copy)]
+pub struct Mat4 {
+    rows: [[f32; 4]; 4]
+}
+
+impl Mat4 {
+    pub fn new(rows: [[f32; 4]; 4]) -> Mat4 {
+        Mat4 { rows: rows }
+    }
+
+    // A point's homogeneous coordinate is 1, so the last column
+    // (translation) applies.
+    pub fn mul_point(&self, p: Point3) -> Point3 {
+        let (x, y, z) = p.channels();
+        let r = self.rows;
+        Point3::new(
+            r[0][0]*x + r[0][1]*y + r[0][2]*z + r[0][3],
+            r[1][0]*x + r[1][1]*y + r[1][2]*z + r[1][3],
+            r[2][0]*x + r[2][1]*y + r[2][2]*z + r[2][3]
+        )
+    }
+
+    // A vector's homogeneous coordinate is 0, so translation drops out.
+    // Deliberately not renormalized afterwards -- callers rely on a hit
+    // `time` found against this vector staying valid back in whichever
+    // space the ray started in.
+    pub fn mul_vec(&self, v: Vec3) -> Vec3 {
+        let r = self.rows;
+        Vec3::new(
+            r[0][0]*v.vals[0] + r[0][1]*v.vals[1] + r[0][2]*v.vals[2],
+            r[1][0]*v.vals[0] + r[1][1]*v.vals[1] + r[1][2]*v.vals[2],
+            r[2][0]*v.vals[0] + r[2][1]*v.vals[1] + r[2][2]*v.vals[2]
+        )
+    }
+
+    pub fn transpose(&self) -> Mat4 {
+        let r = self.rows;
+        Mat4::new([
+            [r[0][0], r[1][0], r[2][0], r[3][0]],
+            [r[0][1], r[1][1], r[2][1], r[3][1]],
+            [r[0][2], r[1][2], r[2][2], r[3][2]],
+            [r[0][3], r[1][3], r[2][3], r[3][3]]
+        ])
+    }
+
+    // Gauss-Jordan elimination with partial pivoting. General enough to
+    // invert any invertible 4x4, not just the affine rotate/scale/translate
+    // matrices passed to Transformed.
+    pub fn inverse(&self) -> Mat4 {
+        let mut a = self.rows;
+        let mut inv = [
+            [1., 0., 0., 0.],
+            [0., 1., 0., 0.],
+            [0., 0., 1., 0.],
+            [0., 0., 0., 1.]
+        ];
+
+        for col in 0..4 {
+            let mut pivot = col;
+            for row in (col + 1)..4 {
+                if a[row][col].abs() > a[pivot][col].abs() {
+                    pivot = row;
+                }
+            }
+            if pivot != col {
+                let tmp = a[col]; a[col] = a[pivot]; a[pivot] = tmp;
+                let tmp = inv[col]; inv[col] = inv[pivot]; inv[pivot] = tmp;
+            }
+
+            let diag = a[col][col];
+            for c in 0..4 {
+                a[col][c] /= diag;
+                inv[col][c] /= diag;
+            }
+
+            for row in 0..4 {
+                if row != col {
+                    let factor = a[row][col];
+                    for c in 0..4 {
+                        a[row][c] -= factor * a[col][c];
+                        inv[row][c] -= factor * inv[col][c];
+                    }
+                }
+            }
+        }
+
+        Mat4::new(inv)
+    }
+}